@@ -0,0 +1,217 @@
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::timeline::{Timeline, Trigger};
+
+type Plan = RefCell<Option<Box<dyn FnOnce(&mut Context)>>>;
+type EventHandler = Rc<dyn Fn(&mut Context, &dyn Any)>;
+
+/// A simulation engine built on top of `Timeline`. Where `Timeline` is a
+/// bare priority queue, `Context` adds the two things independent sim
+/// modules (adventurer state, skill gauges, affliction stacks, ...) need to
+/// cooperate without a monolithic god-struct: a per-type data container
+/// they can lazily initialize, and a typed event bus to react to each
+/// other. All time progression still flows through the underlying
+/// `Timeline`/`Trigger` machinery.
+pub struct Context {
+    timeline: Timeline<Plan>,
+    data_containers: HashMap<TypeId, Box<dyn Any>>,
+    event_handlers: HashMap<TypeId, Vec<EventHandler>>,
+}
+
+/// An opaque handle to a plan scheduled via `Context::add_plan`. The only
+/// thing you can do with it is hand it back to `Context::cancel_plan`; the
+/// underlying `Trigger<Plan>` stays private to this module.
+pub struct PlanId(Rc<Trigger<Plan>>);
+
+impl Context {
+    pub fn new() -> Context {
+        Context {
+            timeline: Timeline::new(),
+            data_containers: HashMap::new(),
+            event_handlers: HashMap::new(),
+        }
+    }
+
+    /// Returns this module's data container, inserting `P::default()` on
+    /// first access.
+    pub fn get_data_container_mut<P: Default + 'static>(&mut self) -> &mut P {
+        self.data_containers
+            .entry(TypeId::of::<P>())
+            .or_insert_with(|| Box::new(P::default()))
+            .downcast_mut::<P>()
+            .expect("data container stored under the wrong TypeId")
+    }
+
+    /// Registers `handler` to run whenever an `E` event is emitted via
+    /// `emit_event`. Handlers are plain `Fn`, not `FnMut`: any state a
+    /// handler needs to carry across events belongs in its own data
+    /// container, not in its closure capture, so that reentrant dispatch
+    /// (a handler emitting another `E` while it is running) never has to
+    /// fight over a `&mut` to the handler itself.
+    pub fn subscribe_to_event<E: 'static>(&mut self, handler: impl Fn(&mut Context, &E) + 'static) {
+        let wrapped: EventHandler = Rc::new(move |ctx, event| {
+            handler(ctx, event.downcast_ref::<E>().expect("event type mismatch"));
+        });
+        self.event_handlers.entry(TypeId::of::<E>()).or_default().push(wrapped);
+    }
+
+    /// Synchronously dispatches `event` to every handler subscribed via
+    /// `subscribe_to_event::<E>`. The handler list is snapshotted (an `Rc`
+    /// clone per handler, not a deep copy) before any handler runs, so a
+    /// handler that emits another `E` from within its own call sees the
+    /// full registry rather than one emptied out by the outer dispatch.
+    pub fn emit_event<E: 'static>(&mut self, event: E) {
+        let type_id = TypeId::of::<E>();
+        let handlers = match self.event_handlers.get(&type_id) {
+            Some(handlers) => handlers.clone(),
+            None => return,
+        };
+        for handler in &handlers {
+            handler(self, &event);
+        }
+    }
+
+    /// Schedules `callback` to run after `delay` simulated time, wrapping
+    /// `Timeline::schedule`.
+    pub fn add_plan(&mut self, delay: f64, callback: impl FnOnce(&mut Context) + 'static) -> PlanId {
+        PlanId(self.timeline.schedule(delay, RefCell::new(Some(Box::new(callback)))))
+    }
+
+    /// Cancels a plan returned by `add_plan`.
+    pub fn cancel_plan(&self, plan: &PlanId) {
+        plan.0.cancel();
+    }
+
+    /// Drives the simulation to completion, running every plan in
+    /// timestamp order (including those scheduled by earlier plans) until
+    /// none remain.
+    pub fn run(&mut self) {
+        while let Some(trigger) = self.timeline.next() {
+            if let Some(callback) = trigger.value().borrow_mut().take() {
+                callback(self);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct Damage(u32);
+
+    #[test]
+    fn emit_dispatches_to_subscribed_handlers() {
+        let mut ctx = Context::new();
+        let total = Rc::new(Cell::new(0u32));
+        let total_handle = total.clone();
+        ctx.subscribe_to_event::<Damage>(move |_, event| {
+            total_handle.set(total_handle.get() + event.0);
+        });
+        ctx.emit_event(Damage(3));
+        ctx.emit_event(Damage(4));
+        assert_eq!(total.get(), 7);
+    }
+
+    #[test]
+    fn emit_with_no_subscribers_is_a_no_op() {
+        let mut ctx = Context::new();
+        ctx.emit_event(Damage(5));
+    }
+
+    #[test]
+    fn reentrant_same_type_emission_still_reaches_every_handler() {
+        let mut ctx = Context::new();
+        let count = Rc::new(Cell::new(0u32));
+
+        let count_a = count.clone();
+        ctx.subscribe_to_event::<Damage>(move |_, _| {
+            count_a.set(count_a.get() + 1);
+        });
+
+        let count_b = count.clone();
+        ctx.subscribe_to_event::<Damage>(move |ctx, event| {
+            count_b.set(count_b.get() + 1);
+            if event.0 < 10 {
+                ctx.emit_event(Damage(event.0 + 10));
+            }
+        });
+
+        ctx.emit_event(Damage(1));
+
+        // Outer emission hits both handlers (2), which re-emits once,
+        // and that nested emission again hits both handlers (2 more).
+        assert_eq!(count.get(), 4);
+    }
+
+    #[test]
+    fn data_containers_are_isolated_per_type() {
+        #[derive(Default)]
+        struct Health(u32);
+        #[derive(Default)]
+        struct Mana(u32);
+
+        let mut ctx = Context::new();
+        ctx.get_data_container_mut::<Health>().0 = 50;
+        ctx.get_data_container_mut::<Mana>().0 = 20;
+
+        assert_eq!(ctx.get_data_container_mut::<Health>().0, 50);
+        assert_eq!(ctx.get_data_container_mut::<Mana>().0, 20);
+    }
+
+    #[test]
+    fn data_container_defaults_on_first_access() {
+        #[derive(Default)]
+        struct Counter(u32);
+
+        let mut ctx = Context::new();
+        assert_eq!(ctx.get_data_container_mut::<Counter>().0, 0);
+    }
+
+    #[test]
+    fn plans_run_in_timestamp_order() {
+        let mut ctx = Context::new();
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let log_a = log.clone();
+        ctx.add_plan(5.0, move |_| log_a.borrow_mut().push("second"));
+        let log_b = log.clone();
+        ctx.add_plan(1.0, move |_| log_b.borrow_mut().push("first"));
+
+        ctx.run();
+        assert_eq!(*log.borrow(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn cancelled_plan_never_runs() {
+        let mut ctx = Context::new();
+        let ran = Rc::new(Cell::new(false));
+        let ran_handle = ran.clone();
+
+        let plan = ctx.add_plan(1.0, move |_| ran_handle.set(true));
+        ctx.cancel_plan(&plan);
+
+        ctx.run();
+        assert!(!ran.get());
+    }
+
+    #[test]
+    fn plans_can_schedule_further_plans() {
+        let mut ctx = Context::new();
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let log_handle = log.clone();
+        ctx.add_plan(1.0, move |ctx| {
+            log_handle.borrow_mut().push("first");
+            let log_handle = log_handle.clone();
+            ctx.add_plan(1.0, move |_| log_handle.borrow_mut().push("second"));
+        });
+
+        ctx.run();
+        assert_eq!(*log.borrow(), vec!["first", "second"]);
+    }
+}
@@ -0,0 +1,13 @@
+//! The simulation engine: a reusable, still-growing API surface. Only a
+//! slice of it is exercised by the demo `main` in `main.rs`, so allow dead code
+//! and re-exports the demo doesn't name directly rather than let them
+//! block the build.
+#![allow(dead_code, unused_imports)]
+
+mod timeline;
+mod context;
+mod executor;
+
+pub use timeline::{Timeline, Trigger};
+pub use context::{Context, PlanId};
+pub use executor::{Executor, JoinHandle, Timer};
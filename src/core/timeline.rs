@@ -1,45 +1,180 @@
 use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
 use std::cell::{RefCell, Cell};
+use std::hash::Hash;
 use std::rc::{Rc, Weak};
 use std::any::Any;
 
-pub struct Timeline<T> {
+/// `K` is the type of key used by [`Timeline::schedule_keyed`] /
+/// [`Timeline::cancel_all`] for bulk cancellation. Timelines constructed
+/// with the plain [`Timeline::new`] default `K` to `()` and never touch the
+/// keyed bucket map; use [`Timeline::new_keyed`] to pick a real key type.
+pub struct Timeline<T, K = ()> {
     time: f64,
+    next_seq: u64,
     queue: BinaryHeap<Rc<Trigger<T>>>,
+    keyed: HashMap<K, Vec<Weak<Trigger<T>>>>,
 }
 
-impl<T> Timeline<T> {
-    pub fn new() -> Timeline<T> {
+impl<T, K> Timeline<T, K> {
+    fn empty() -> Timeline<T, K> {
         Timeline {
             time: 0.0,
+            next_seq: 0,
             queue: BinaryHeap::new(),
+            keyed: HashMap::new(),
         }
     }
 
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
     pub fn schedule(&mut self, delay: f64, value: T) -> Rc<Trigger<T>> {
         self.schedule_abs(self.time + delay, value)
     }
 
     pub fn schedule_abs(&mut self, time: f64, value: T) -> Rc<Trigger<T>> {
         assert!(time >= self.time);
+        let seq = self.next_seq;
+        self.next_seq += 1;
         let r = Rc::new(Trigger {
             time,
+            seq,
             value,
-            cancelled: Cell::new(false),
+            cancelled: Rc::new(Cell::new(false)),
+            periodic: None,
+        });
+        self.queue.push(r.clone());
+        r
+    }
+}
+
+impl<T: Clone + 'static, K> Timeline<T, K> {
+    /// Schedules a self-rescheduling series: `value` first fires after
+    /// `first_delay`, then again every `interval` thereafter, forever,
+    /// until the returned handle is cancelled. Each firing hands out a
+    /// fresh clone of `value` (the original is never itself mutated).
+    /// Cancelling the returned `Trigger` stops every future occurrence,
+    /// since the whole series shares one cancellation flag.
+    pub fn schedule_periodic(&mut self, first_delay: f64, interval: f64, value: T) -> Rc<Trigger<T>> {
+        let regenerate: Rc<dyn Fn() -> T> = Rc::new(move || value.clone());
+        self.schedule_periodic_entry(first_delay, interval, regenerate, Rc::new(Cell::new(false)))
+    }
+
+    fn schedule_periodic_entry(
+        &mut self,
+        delay: f64,
+        interval: f64,
+        regenerate: Rc<dyn Fn() -> T>,
+        cancelled: Rc<Cell<bool>>,
+    ) -> Rc<Trigger<T>> {
+        let time = self.time + delay;
+        assert!(time >= self.time);
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        let r = Rc::new(Trigger {
+            time,
+            seq,
+            value: regenerate(),
+            cancelled,
+            periodic: Some((interval, regenerate)),
         });
         self.queue.push(r.clone());
         r
     }
 }
 
-impl<T> Iterator for Timeline<T> {
+impl<T> Timeline<T> {
+    pub fn new() -> Timeline<T> {
+        Timeline::empty()
+    }
+}
+
+impl<T, K> Timeline<T, K> {
+    /// Returns the timestamp of the earliest non-cancelled trigger, if any,
+    /// without popping it or advancing `self.time`. Cancelled entries at the
+    /// front of the queue are skipped (and, since they're dead weight, also
+    /// dropped) so the peeked time always matches what `next()` would return.
+    pub fn peek_next_time(&mut self) -> Option<f64> {
+        while let Some(next) = self.queue.peek() {
+            if next.cancelled.get() {
+                self.queue.pop();
+                continue;
+            }
+            return Some(next.time);
+        }
+        None
+    }
+
+    /// Fires every trigger due at or before `t`, in order, then advances
+    /// `self.time` to `t` regardless of whether any triggers remained.
+    /// Panics if `t` is behind the current time, same as `schedule_abs`.
+    pub fn advance_to(&mut self, t: f64) {
+        assert!(t >= self.time);
+        while let Some(next_time) = self.peek_next_time() {
+            if next_time > t {
+                break;
+            }
+            self.next();
+        }
+        self.time = t;
+    }
+}
+
+impl<T, K: Hash + Eq + Clone> Timeline<T, K> {
+    /// Like [`Timeline::new`], but for a timeline that will use
+    /// [`Timeline::schedule_keyed`] / [`Timeline::cancel_all`] with keys of
+    /// type `K`.
+    pub fn new_keyed() -> Timeline<T, K> {
+        Timeline::empty()
+    }
+
+    /// Like [`Timeline::schedule`], but attaches `key` so every live event
+    /// sharing that key can later be cancelled in one shot with
+    /// [`Timeline::cancel_all`].
+    pub fn schedule_keyed(&mut self, delay: f64, key: K, value: T) -> Rc<Trigger<T>> {
+        let trigger = self.schedule(delay, value);
+        let bucket = self.keyed.entry(key).or_default();
+        bucket.retain(|w| w.strong_count() > 0);
+        bucket.push(Rc::downgrade(&trigger));
+        trigger
+    }
+
+    /// Cancels every live event scheduled under `key` via
+    /// [`Timeline::schedule_keyed`]. Cancelled and already-fired events are
+    /// dropped from the bucket, which is then empty until reused.
+    pub fn cancel_all(&mut self, key: &K) {
+        if let Some(bucket) = self.keyed.remove(key) {
+            for weak in bucket {
+                if let Some(trigger) = weak.upgrade() {
+                    trigger.cancel();
+                }
+            }
+        }
+    }
+}
+
+impl<T, K> Iterator for Timeline<T, K> {
     type Item = Rc<Trigger<T>>;
 
     fn next(&mut self) -> Option<Rc<Trigger<T>>> {
         while let Some(next) = self.queue.pop() {
             if next.cancelled.get() { continue; }
             self.time = next.time;
+            if let Some((interval, regenerate)) = &next.periodic {
+                if !next.cancelled.get() {
+                    let seq = self.next_seq;
+                    self.next_seq += 1;
+                    self.queue.push(Rc::new(Trigger {
+                        time: next.time + interval,
+                        seq,
+                        value: regenerate(),
+                        cancelled: next.cancelled.clone(),
+                        periodic: Some((*interval, regenerate.clone())),
+                    }));
+                }
+            }
             return Some(next)
         }
         None
@@ -48,21 +183,34 @@ impl<T> Iterator for Timeline<T> {
 
 pub struct Trigger<T> {
     time: f64,
+    seq: u64,
     value: T,
-    cancelled: Cell<bool>,
+    cancelled: Rc<Cell<bool>>,
+    /// `(interval, regenerate)` for triggers created by `schedule_periodic`;
+    /// `regenerate` produces the next occurrence's value without requiring
+    /// every `Timeline<T>` to bound `T: Clone`.
+    periodic: Option<(f64, Rc<dyn Fn() -> T>)>,
 }
 
 impl<T> Trigger<T> {
     pub fn cancel(&self) {
         self.cancelled.set(true);
     }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
 }
 
 impl<T> Eq for Trigger<T> {}
 
 impl<T> PartialEq for Trigger<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.time == other.time
+        self.time == other.time && self.seq == other.seq
     }
 }
 
@@ -74,7 +222,12 @@ impl<T> Ord for Trigger<T> {
 
 impl<T> PartialOrd for Trigger<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        other.time.partial_cmp(&self.time)
+        // `BinaryHeap` is a max-heap, so both comparisons are inverted: the
+        // earliest `time` and, among ties, the lowest `seq` (the one
+        // inserted first) must compare as "greatest" to come out first.
+        other.time.partial_cmp(&self.time).map(|ord| {
+            ord.then_with(|| other.seq.cmp(&self.seq))
+        })
     }
 }
 
@@ -154,6 +307,125 @@ mod tests {
         assert!(tl.next().is_none())
     }
 
+    #[test]
+    fn tie_breaking_is_fifo() {
+        let mut tl = Timeline::new();
+        tl.schedule(1.0, 'a');
+        tl.schedule(1.0, 'b');
+        tl.schedule(1.0, 'c');
+
+        assert_eq!(tl.next().unwrap().value, 'a');
+        assert_eq!(tl.next().unwrap().value, 'b');
+        assert_eq!(tl.next().unwrap().value, 'c');
+        assert!(tl.next().is_none());
+    }
+
+    #[test]
+    fn cancel_all_drops_only_keyed_events() {
+        let mut tl: Timeline<i32, &str> = Timeline::new_keyed();
+        tl.schedule_keyed(1.0, "unit-a", 1);
+        tl.schedule_keyed(2.0, "unit-a", 2);
+        tl.schedule_keyed(1.5, "unit-b", 3);
+
+        tl.cancel_all(&"unit-a");
+
+        let n = tl.next().unwrap();
+        assert_eq!(n.value, 3);
+        assert!(tl.next().is_none());
+    }
+
+    #[test]
+    fn cancel_all_on_unused_key_is_a_no_op() {
+        let mut tl: Timeline<i32, &str> = Timeline::new_keyed();
+        tl.schedule(1.0, 1);
+        tl.cancel_all(&"nothing-scheduled-under-this-key");
+        assert_eq!(tl.next().unwrap().value, 1);
+    }
+
+    #[test]
+    fn peek_next_time_does_not_consume() {
+        let mut tl = Timeline::new();
+        tl.schedule(3.0, 3.0);
+        tl.schedule(1.0, 1.0);
+
+        assert_eq!(tl.peek_next_time(), Some(1.0));
+        assert_eq!(tl.peek_next_time(), Some(1.0));
+        assert_eq!(tl.time, 0.0);
+
+        assert_eq!(tl.next().unwrap().value, 1.0);
+        assert_eq!(tl.peek_next_time(), Some(3.0));
+    }
+
+    #[test]
+    fn peek_next_time_skips_cancelled() {
+        let mut tl = Timeline::new();
+        tl.schedule(1.0, 1.0).cancel();
+        tl.schedule(2.0, 2.0);
+
+        assert_eq!(tl.peek_next_time(), Some(2.0));
+    }
+
+    #[test]
+    fn advance_to_fires_everything_due() {
+        let mut tl = Timeline::new();
+        tl.schedule(3.0, 3.0);
+        tl.schedule(1.0, 1.0);
+        tl.schedule(2.0, 2.0);
+        tl.schedule(5.0, 5.0);
+
+        tl.advance_to(4.0);
+        assert_eq!(tl.time, 4.0);
+
+        // advance_to itself doesn't hand back fired values, so drain what's
+        // left to prove only the <= 4.0 triggers were consumed.
+        let mut fired = Vec::new();
+        while let Some(n) = tl.next() {
+            fired.push(n.value);
+        }
+        assert_eq!(fired, vec![5.0]);
+    }
+
+    #[test]
+    fn advance_to_sets_time_with_nothing_pending() {
+        let mut tl: Timeline<f64> = Timeline::new();
+        tl.advance_to(10.0);
+        assert_eq!(tl.time, 10.0);
+    }
+
+    #[should_panic]
+    #[test]
+    fn advance_to_rejects_past_time() {
+        let mut tl = Timeline::new();
+        tl.schedule(10.0, ());
+        tl.next();
+        tl.advance_to(9.0);
+    }
+
+    #[test]
+    fn periodic_reschedules_until_cancelled() {
+        let mut tl = Timeline::new();
+        let handle = tl.schedule_periodic(1.0, 1.0, "tick");
+
+        assert_eq!(tl.next().unwrap().value, "tick");
+        assert_eq!(tl.time, 1.0);
+        assert_eq!(tl.next().unwrap().value, "tick");
+        assert_eq!(tl.time, 2.0);
+
+        handle.cancel();
+        assert!(tl.next().is_none());
+    }
+
+    #[test]
+    fn periodic_clones_value_per_occurrence() {
+        let mut tl = Timeline::new();
+        tl.schedule_periodic(1.0, 1.0, vec![1, 2, 3]);
+
+        let a = tl.next().unwrap();
+        let b = tl.next().unwrap();
+        assert_eq!(a.value, vec![1, 2, 3]);
+        assert_eq!(b.value, vec![1, 2, 3]);
+    }
+
     #[test]
     fn closures_in_timeline() {
         struct Foo {
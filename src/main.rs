@@ -1,13 +1,19 @@
 mod core;
 
+use core::{Context, Executor};
+
 fn main() {
-    let mut tl = core::Timeline::new(0.0);
-    tl.schedule(5.0, || println!("Hi 5"));
-    tl.schedule(8.0,|| println!("Hi 8"));
-    tl.schedule(4.0, || println!("Hi 4"));
-    tl.show();
-    tl.next();
-    tl.show();
-    tl.next();
-    tl.show();
+    let mut ctx = Context::new();
+    ctx.add_plan(4.0, |_| println!("Hi 4"));
+    ctx.add_plan(5.0, |_| println!("Hi 5"));
+    ctx.add_plan(8.0, |_| println!("Hi 8"));
+    ctx.run();
+
+    let exec = Executor::new();
+    let timer = exec.timer(2.0);
+    exec.spawn(async move {
+        timer.await;
+        println!("woke up after 2 simulated units");
+    });
+    exec.run();
 }